@@ -1,15 +1,149 @@
 use std::collections::VecDeque;
 use std::fmt;
 use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+/// Error returned by `Sender::send` when every `Receiver` has been dropped.
+///
+/// The message that could not be delivered is recovered via `into_inner`.
+pub struct SendError<T>(pub T);
+
+impl<T> SendError<T> {
+    /// Consumes the error, returning the message that failed to send.
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> fmt::Debug for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SendError(..)")
+    }
+}
+
+impl<T> fmt::Display for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "sending on a closed channel")
+    }
+}
+
+impl<T> std::error::Error for SendError<T> {}
+
+/// Error returned by `Receiver::recv` when the queue is empty and every
+/// `Sender` has been dropped.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct RecvError;
+
+impl fmt::Display for RecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "receiving on an empty and closed channel")
+    }
+}
+
+impl std::error::Error for RecvError {}
+
+/// Error returned by `Receiver::try_recv`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum TryRecvError {
+    /// The queue is currently empty, but senders remain.
+    Empty,
+    /// The queue is empty and every `Sender` has dropped.
+    Disconnected,
+}
+
+impl fmt::Display for TryRecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TryRecvError::Empty => write!(f, "receiving on an empty channel"),
+            TryRecvError::Disconnected => write!(f, "receiving on an empty and closed channel"),
+        }
+    }
+}
+
+impl std::error::Error for TryRecvError {}
+
+/// Error returned by `Receiver::recv_timeout`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum RecvTimeoutError {
+    /// No message arrived before the timeout elapsed.
+    Timeout,
+    /// The queue is empty and every `Sender` has dropped.
+    Disconnected,
+}
+
+impl fmt::Display for RecvTimeoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecvTimeoutError::Timeout => write!(f, "timed out waiting on channel"),
+            RecvTimeoutError::Disconnected => write!(f, "receiving on an empty and closed channel"),
+        }
+    }
+}
+
+impl std::error::Error for RecvTimeoutError {}
 
 struct Inner<T> {
     queue: VecDeque<T>,
     senders: usize,
+    receivers: usize,
+    /// `None` for the unbounded `channel`; `Some(n)` for `sync_channel(n)`,
+    /// where `n == 0` means rendezvous (no buffering at all).
+    capacity: Option<usize>,
+    /// Receivers currently blocked waiting for a message, used by
+    /// `SyncSender` to detect a rendezvous partner.
+    waiting_receivers: usize,
+}
+
+/// Wakeup registered by an in-progress `Select::select`. `ready` is set
+/// under the same lock the selector waits on, so a notification that races
+/// the selector's own check-then-wait can never be silently dropped: by the
+/// time the selector manages to lock `ready`, it is already `true`.
+struct SelectWaker {
+    ready: Mutex<bool>,
+    condvar: Condvar,
+}
+
+impl SelectWaker {
+    fn new() -> Self {
+        Self {
+            ready: Mutex::new(false),
+            condvar: Condvar::new(),
+        }
+    }
+
+    fn signal(&self) {
+        let mut ready = self.ready.lock().unwrap();
+        *ready = true;
+        self.condvar.notify_all();
+    }
+
+    /// Blocks until `signal` has been called at least once since the last
+    /// `wait`.
+    fn wait(&self) {
+        let ready = self.ready.lock().unwrap();
+        let mut ready = self.condvar.wait_while(ready, |ready| !*ready).unwrap();
+        *ready = false;
+    }
 }
 
 struct Shared<T> {
     inner: Mutex<Inner<T>>,
     available: Condvar,
+    /// Signalled whenever room frees up in a bounded queue, or a receiver
+    /// starts waiting (for the rendezvous case).
+    space_available: Condvar,
+    /// Wakers registered by an in-progress `Select::select`, signalled
+    /// alongside `available` so a selector blocked on several receivers
+    /// wakes as soon as any one of them becomes ready.
+    selectors: Mutex<Vec<Arc<SelectWaker>>>,
+}
+
+impl<T> Shared<T> {
+    fn notify_selectors(&self) {
+        for waker in self.selectors.lock().unwrap().iter() {
+            waker.signal();
+        }
+    }
 }
 
 // Sender<T>
@@ -33,11 +167,14 @@ impl<T> Drop for Sender<T> {
     fn drop(&mut self) {
         let mut q_lock = self.shared.inner.lock().unwrap();
         q_lock.senders -= 1;
-        let was_last = q_lock.senders == 1;
+        let was_last = q_lock.senders == 0;
+        drop(q_lock);
         if was_last {
-            self.shared.available.notify_one();
+            // Every blocked receiver needs to wake up and observe closure,
+            // not just one of them.
+            self.shared.available.notify_all();
+            self.shared.notify_selectors();
         }
-        drop(q_lock);
     }
 }
 
@@ -50,10 +187,18 @@ impl<T: std::fmt::Debug> std::fmt::Display for Sender<T> {
 }
 
 impl<T> Sender<T> {
-    fn send(&self, msg: T) {
-        self.shared.inner.lock().unwrap().queue.push_back(msg);
-        drop(self.shared.inner.lock());
+    /// Sends `msg` on the channel, returning `Err` with the message if every
+    /// `Receiver` has already been dropped.
+    pub fn send(&self, msg: T) -> Result<(), SendError<T>> {
+        let mut inner = self.shared.inner.lock().unwrap();
+        if inner.receivers == 0 {
+            return Err(SendError(msg));
+        }
+        inner.queue.push_back(msg);
+        drop(inner);
         self.shared.available.notify_one();
+        self.shared.notify_selectors();
+        Ok(())
     }
 }
 
@@ -62,6 +207,17 @@ pub struct Receiver<T> {
     shared: Arc<Shared<T>>,
 }
 
+impl<T> Clone for Receiver<T> {
+    fn clone(&self) -> Self {
+        let mut inner = self.shared.inner.lock().unwrap();
+        inner.receivers += 1;
+        drop(inner);
+        Self {
+            shared: Arc::clone(&self.shared),
+        }
+    }
+}
+
 impl<T: std::fmt::Debug> std::fmt::Display for Receiver<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let q_lock = self.shared.inner.lock().unwrap();
@@ -69,31 +225,177 @@ impl<T: std::fmt::Debug> std::fmt::Display for Receiver<T> {
         write!(f, "Sender: {}, Deque: {:?}", num_senders, q_lock.queue)
     }
 }
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        let mut inner = self.shared.inner.lock().unwrap();
+        inner.receivers -= 1;
+        let was_last = inner.receivers == 0;
+        drop(inner);
+        if was_last {
+            // A `SyncSender::send` blocked on a full queue (or a
+            // rendezvous with no one waiting) needs to wake up and observe
+            // `receivers == 0` so it can return `Err` instead of hanging.
+            self.shared.space_available.notify_all();
+            self.shared.notify_selectors();
+        }
+    }
+}
+
 impl<T> Receiver<T> {
-    fn receive(&mut self) -> Option<T> {
+    /// Blocks until a message is available, returning `Err(RecvError)` once
+    /// the queue is empty and every `Sender` has dropped.
+    pub fn recv(&mut self) -> Result<T, RecvError> {
         let mut inner = self.shared.inner.lock().unwrap();
         loop {
             match inner.queue.pop_front() {
-                Some(msg) => return Some(msg),
-                None if inner.senders == 0 => {
-                    println!("#senders {}", inner.senders);
-                    return None;
+                Some(msg) => {
+                    drop(inner);
+                    self.shared.space_available.notify_one();
+                    return Ok(msg);
                 }
+                None if inner.senders == 0 => return Err(RecvError),
                 None => {
+                    inner.waiting_receivers += 1;
+                    self.shared.space_available.notify_one();
                     inner = self.shared.available.wait(inner).unwrap();
+                    inner.waiting_receivers -= 1;
+                }
+            }
+        }
+    }
+
+    /// Thin wrapper over `recv` for callers that don't care why the channel
+    /// is closed.
+    pub fn receive(&mut self) -> Option<T> {
+        self.recv().ok()
+    }
+
+    /// Returns a message if one is immediately available, without blocking.
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        let mut inner = self.shared.inner.lock().unwrap();
+        match inner.queue.pop_front() {
+            Some(msg) => {
+                drop(inner);
+                self.shared.space_available.notify_one();
+                Ok(msg)
+            }
+            None if inner.senders == 0 => Err(TryRecvError::Disconnected),
+            None => Err(TryRecvError::Empty),
+        }
+    }
+
+    /// Blocks until a message is available or `timeout` elapses, whichever
+    /// comes first.
+    pub fn recv_timeout(&mut self, timeout: Duration) -> Result<T, RecvTimeoutError> {
+        let deadline = Instant::now() + timeout;
+        let mut inner = self.shared.inner.lock().unwrap();
+        loop {
+            match inner.queue.pop_front() {
+                Some(msg) => {
+                    drop(inner);
+                    self.shared.space_available.notify_one();
+                    return Ok(msg);
+                }
+                None if inner.senders == 0 => return Err(RecvTimeoutError::Disconnected),
+                None => {
+                    let remaining = match deadline.checked_duration_since(Instant::now()) {
+                        Some(remaining) => remaining,
+                        None => return Err(RecvTimeoutError::Timeout),
+                    };
+                    inner.waiting_receivers += 1;
+                    self.shared.space_available.notify_one();
+                    let (guard, result) = self
+                        .shared
+                        .available
+                        .wait_timeout(inner, remaining)
+                        .unwrap();
+                    inner = guard;
+                    inner.waiting_receivers -= 1;
+                    if result.timed_out() && inner.queue.is_empty() {
+                        return Err(RecvTimeoutError::Timeout);
+                    }
                 }
             }
         }
-        // None
+    }
+
+    /// Returns a borrowing iterator that blocks on each call to `next`,
+    /// stopping once every `Sender` has dropped and the queue is drained.
+    pub fn iter(&mut self) -> Iter<'_, T> {
+        Iter { rx: self }
+    }
+
+    /// Returns an iterator that drains only the currently-buffered items,
+    /// stopping at the first empty state instead of blocking.
+    pub fn try_iter(&mut self) -> TryIter<'_, T> {
+        TryIter { rx: self }
+    }
+}
+
+/// Borrowing iterator returned by `Receiver::iter`.
+pub struct Iter<'a, T> {
+    rx: &'a mut Receiver<T>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = T;
+    fn next(&mut self) -> Option<T> {
+        self.rx.receive()
+    }
+}
+
+/// Non-blocking iterator returned by `Receiver::try_iter`.
+pub struct TryIter<'a, T> {
+    rx: &'a mut Receiver<T>,
+}
+
+impl<'a, T> Iterator for TryIter<'a, T> {
+    type Item = T;
+    fn next(&mut self) -> Option<T> {
+        self.rx.try_recv().ok()
+    }
+}
+
+/// Owning iterator returned by `Receiver`'s `IntoIterator` impl.
+pub struct IntoIter<T> {
+    rx: Receiver<T>,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+    fn next(&mut self) -> Option<T> {
+        self.rx.receive()
+    }
+}
+
+impl<T> IntoIterator for Receiver<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter { rx: self }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut Receiver<T> {
+    type Item = T;
+    type IntoIter = Iter<'a, T>;
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
     }
 }
+
 pub fn channel<T>() -> (Sender<T>, Receiver<T>) {
     let shared = Shared {
         inner: Mutex::new(Inner {
             queue: VecDeque::new(),
             senders: 1,
+            receivers: 1,
+            capacity: None,
+            waiting_receivers: 0,
         }),
         available: Condvar::new(),
+        space_available: Condvar::new(),
+        selectors: Mutex::new(Vec::new()),
     };
 
     let shared = Arc::new(shared);
@@ -109,6 +411,426 @@ pub fn channel<T>() -> (Sender<T>, Receiver<T>) {
     )
 }
 
+/// A `Sender` handle for a [`sync_channel`], whose `send` blocks while the
+/// bounded queue is full instead of growing it without limit.
+pub struct SyncSender<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> Clone for SyncSender<T> {
+    fn clone(&self) -> Self {
+        let mut inner = self.shared.inner.lock().unwrap();
+        inner.senders += 1;
+        drop(inner);
+        Self {
+            shared: Arc::clone(&self.shared),
+        }
+    }
+}
+
+impl<T> Drop for SyncSender<T> {
+    fn drop(&mut self) {
+        let mut inner = self.shared.inner.lock().unwrap();
+        inner.senders -= 1;
+        let was_last = inner.senders == 0;
+        drop(inner);
+        if was_last {
+            self.shared.available.notify_all();
+            self.shared.notify_selectors();
+        }
+    }
+}
+
+impl<T> SyncSender<T> {
+    /// Sends `msg`, blocking while the bounded queue is full.
+    ///
+    /// With `capacity == 0` this blocks until a `Receiver` is actively
+    /// waiting to take the value (rendezvous semantics).
+    pub fn send(&self, msg: T) -> Result<(), SendError<T>> {
+        let mut inner = self.shared.inner.lock().unwrap();
+        let capacity = inner.capacity.expect("SyncSender channel always has a capacity");
+        loop {
+            if inner.receivers == 0 {
+                return Err(SendError(msg));
+            }
+            let has_room = if capacity == 0 {
+                inner.queue.is_empty() && inner.waiting_receivers > 0
+            } else {
+                inner.queue.len() < capacity
+            };
+            if has_room {
+                inner.queue.push_back(msg);
+                drop(inner);
+                self.shared.available.notify_one();
+                self.shared.notify_selectors();
+                return Ok(());
+            }
+            inner = self.shared.space_available.wait(inner).unwrap();
+        }
+    }
+}
+
+/// Creates a bounded channel that holds at most `capacity` messages.
+///
+/// `SyncSender::send` blocks while the queue is full instead of growing it
+/// without limit, giving callers backpressure. A `capacity` of `0` is a
+/// rendezvous channel: `send` blocks until a `Receiver` is waiting to take
+/// the value directly.
+pub fn sync_channel<T>(capacity: usize) -> (SyncSender<T>, Receiver<T>) {
+    let shared = Shared {
+        inner: Mutex::new(Inner {
+            queue: VecDeque::new(),
+            senders: 1,
+            receivers: 1,
+            capacity: Some(capacity),
+            waiting_receivers: 0,
+        }),
+        available: Condvar::new(),
+        space_available: Condvar::new(),
+        selectors: Mutex::new(Vec::new()),
+    };
+
+    let shared = Arc::new(shared);
+    (
+        SyncSender {
+            shared: shared.clone(),
+        },
+        Receiver {
+            shared: shared.clone(),
+        },
+    )
+}
+
+/// Blocks until any one of several registered `Receiver`s has a dequeuable
+/// item or has been disconnected, returning its index so the caller can then
+/// `try_recv` from it.
+/// Lets a dispatcher service several independent channels from one thread
+/// without busy-polling each in turn.
+pub struct Select<'a, T> {
+    receivers: Vec<&'a Receiver<T>>,
+}
+
+impl<'a, T> Select<'a, T> {
+    pub fn new() -> Self {
+        Self {
+            receivers: Vec::new(),
+        }
+    }
+
+    /// Registers `rx`, returning the index `select`/`ready` will report it
+    /// under.
+    pub fn add(&mut self, rx: &'a Receiver<T>) -> usize {
+        self.receivers.push(rx);
+        self.receivers.len() - 1
+    }
+
+    fn ready_index(&self) -> Option<usize> {
+        self.receivers.iter().position(|rx| {
+            let inner = rx.shared.inner.lock().unwrap();
+            !inner.queue.is_empty() || inner.senders == 0
+        })
+    }
+
+    /// Blocks until at least one registered receiver has a message or has
+    /// been disconnected, returning its index.
+    pub fn select(&self) -> usize {
+        // Checked before registering a wakeup so an already-ready channel
+        // never waits at all.
+        if let Some(idx) = self.ready_index() {
+            return idx;
+        }
+        let waker = Arc::new(SelectWaker::new());
+        for rx in &self.receivers {
+            rx.shared.selectors.lock().unwrap().push(Arc::clone(&waker));
+            // Count as a waiting receiver so a rendezvous `sync_channel(0)`
+            // sees someone ready to take the value; SyncSender::send only
+            // ever checks this counter, never the selector registry.
+            let mut inner = rx.shared.inner.lock().unwrap();
+            inner.waiting_receivers += 1;
+            drop(inner);
+            rx.shared.space_available.notify_one();
+        }
+        let result = loop {
+            // `send` (and the last `Sender`/`Receiver` dropping) signals
+            // `waker` under its own lock before notifying, so by the time
+            // `waker.wait()` manages to take that lock it is already
+            // `true` if a wakeup raced this check — it cannot block past
+            // a wakeup that already happened, unlike a plain poll loop.
+            if let Some(idx) = self.ready_index() {
+                break idx;
+            }
+            waker.wait();
+        };
+        for rx in &self.receivers {
+            rx.shared
+                .selectors
+                .lock()
+                .unwrap()
+                .retain(|w| !Arc::ptr_eq(w, &waker));
+            rx.shared.inner.lock().unwrap().waiting_receivers -= 1;
+        }
+        result
+    }
+
+    /// Alias for `select`, matching the `ready`/`select` naming convention
+    /// used by other multi-channel polling APIs.
+    pub fn ready(&self) -> usize {
+        self.select()
+    }
+}
+
+impl<'a, T> Default for Select<'a, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A fan-out channel where every subscribed `Receiver` sees every message,
+/// as opposed to the single-consumer queue above where each message goes
+/// to exactly one receiver.
+pub mod broadcast {
+    use std::sync::{Arc, Condvar, Mutex};
+
+    /// Error returned by `Sender::send` when every `Receiver` has been dropped.
+    pub struct SendError<T>(pub T);
+
+    impl<T> SendError<T> {
+        /// Consumes the error, returning the message that failed to send.
+        pub fn into_inner(self) -> T {
+            self.0
+        }
+    }
+
+    impl<T> std::fmt::Debug for SendError<T> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "SendError(..)")
+        }
+    }
+
+    impl<T> std::fmt::Display for SendError<T> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "sending on a closed broadcast channel")
+        }
+    }
+
+    impl<T> std::error::Error for SendError<T> {}
+
+    /// Error returned by `Receiver::recv`.
+    #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+    pub enum RecvError {
+        /// Every `Sender` has dropped and there are no more messages to read.
+        Closed,
+        /// The receiver fell more than `capacity` messages behind the
+        /// writer; it has been fast-forwarded to the oldest retained
+        /// message and this many were skipped.
+        Lagged(u64),
+    }
+
+    impl std::fmt::Display for RecvError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                RecvError::Closed => write!(f, "receiving on a closed broadcast channel"),
+                RecvError::Lagged(n) => write!(f, "receiver lagged behind by {} messages", n),
+            }
+        }
+    }
+
+    impl std::error::Error for RecvError {}
+
+    struct Inner<T> {
+        buffer: Vec<Option<T>>,
+        capacity: u64,
+        write_pos: u64,
+        senders: usize,
+        receivers: usize,
+    }
+
+    struct Shared<T> {
+        inner: Mutex<Inner<T>>,
+        available: Condvar,
+    }
+
+    pub struct Sender<T> {
+        shared: Arc<Shared<T>>,
+    }
+
+    impl<T> Clone for Sender<T> {
+        fn clone(&self) -> Self {
+            let mut inner = self.shared.inner.lock().unwrap();
+            inner.senders += 1;
+            drop(inner);
+            Self {
+                shared: Arc::clone(&self.shared),
+            }
+        }
+    }
+
+    impl<T> Drop for Sender<T> {
+        fn drop(&mut self) {
+            let mut inner = self.shared.inner.lock().unwrap();
+            inner.senders -= 1;
+            let was_last = inner.senders == 0;
+            drop(inner);
+            if was_last {
+                self.shared.available.notify_all();
+            }
+        }
+    }
+
+    impl<T: Clone> Sender<T> {
+        /// Delivers `msg` to every currently-subscribed `Receiver`, or
+        /// returns it back in `Err` if every `Receiver` has dropped.
+        pub fn send(&self, msg: T) -> Result<(), SendError<T>> {
+            let mut inner = self.shared.inner.lock().unwrap();
+            if inner.receivers == 0 {
+                return Err(SendError(msg));
+            }
+            let idx = (inner.write_pos % inner.capacity) as usize;
+            inner.buffer[idx] = Some(msg);
+            inner.write_pos += 1;
+            drop(inner);
+            self.shared.available.notify_all();
+            Ok(())
+        }
+
+        /// Creates a new `Receiver` that starts reading from the current
+        /// write position, seeing only messages sent after this call.
+        pub fn subscribe(&self) -> Receiver<T> {
+            let mut inner = self.shared.inner.lock().unwrap();
+            let cursor = inner.write_pos;
+            inner.receivers += 1;
+            drop(inner);
+            Receiver {
+                shared: Arc::clone(&self.shared),
+                cursor,
+            }
+        }
+    }
+
+    pub struct Receiver<T> {
+        shared: Arc<Shared<T>>,
+        cursor: u64,
+    }
+
+    impl<T> Drop for Receiver<T> {
+        fn drop(&mut self) {
+            let mut inner = self.shared.inner.lock().unwrap();
+            inner.receivers -= 1;
+            drop(inner);
+        }
+    }
+
+    impl<T: Clone> Receiver<T> {
+        /// Blocks until the next message is available, returning
+        /// `Err(RecvError::Lagged(n))` if this receiver fell too far behind
+        /// and `Err(RecvError::Closed)` once every `Sender` has dropped.
+        pub fn recv(&mut self) -> Result<T, RecvError> {
+            let mut inner = self.shared.inner.lock().unwrap();
+            loop {
+                let behind = inner.write_pos - self.cursor;
+                if behind == 0 {
+                    if inner.senders == 0 {
+                        return Err(RecvError::Closed);
+                    }
+                    inner = self.shared.available.wait(inner).unwrap();
+                    continue;
+                }
+                if behind > inner.capacity {
+                    let missed = behind - inner.capacity;
+                    self.cursor = inner.write_pos - inner.capacity;
+                    return Err(RecvError::Lagged(missed));
+                }
+                let idx = (self.cursor % inner.capacity) as usize;
+                let value = inner.buffer[idx]
+                    .clone()
+                    .expect("slot within the retained window is always populated");
+                self.cursor += 1;
+                return Ok(value);
+            }
+        }
+    }
+
+    /// Creates a broadcast channel backed by a ring buffer of `capacity`
+    /// slots; every message sent is delivered to every subscribed
+    /// `Receiver` rather than consumed by just one.
+    pub fn broadcast<T: Clone>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+        assert!(capacity > 0, "broadcast channel capacity must be non-zero");
+        let shared = Shared {
+            inner: Mutex::new(Inner {
+                buffer: vec![None; capacity],
+                capacity: capacity as u64,
+                write_pos: 0,
+                senders: 1,
+                receivers: 1,
+            }),
+            available: Condvar::new(),
+        };
+        let shared = Arc::new(shared);
+        let sender = Sender {
+            shared: shared.clone(),
+        };
+        let receiver = Receiver {
+            shared: shared.clone(),
+            cursor: 0,
+        };
+        (sender, receiver)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn every_subscriber_sees_every_message() {
+            let (tx, mut rx1) = broadcast::<usize>(4);
+            let mut rx2 = tx.subscribe();
+            tx.send(1).unwrap();
+            tx.send(2).unwrap();
+            assert_eq!(rx1.recv(), Ok(1));
+            assert_eq!(rx1.recv(), Ok(2));
+            assert_eq!(rx2.recv(), Ok(1));
+            assert_eq!(rx2.recv(), Ok(2));
+        }
+
+        #[test]
+        fn slow_receiver_lags() {
+            let (tx, mut rx) = broadcast::<usize>(2);
+            for i in 0..5 {
+                tx.send(i).unwrap();
+            }
+            assert_eq!(rx.recv(), Err(RecvError::Lagged(3)));
+            assert_eq!(rx.recv(), Ok(3));
+            assert_eq!(rx.recv(), Ok(4));
+        }
+
+        #[test]
+        fn recv_closes_once_senders_drop() {
+            let (tx, mut rx) = broadcast::<usize>(2);
+            tx.send(1).unwrap();
+            drop(tx);
+            assert_eq!(rx.recv(), Ok(1));
+            assert_eq!(rx.recv(), Err(RecvError::Closed));
+        }
+
+        #[test]
+        fn send_errors_once_every_receiver_drops() {
+            let (tx, rx) = broadcast::<usize>(2);
+            drop(rx);
+            let err = tx.send(1).unwrap_err();
+            assert_eq!(err.into_inner(), 1);
+        }
+
+        #[test]
+        fn send_still_succeeds_while_one_of_several_receivers_remains() {
+            let (tx, rx1) = broadcast::<usize>(2);
+            let rx2 = tx.subscribe();
+            drop(rx1);
+            tx.send(1).unwrap();
+            drop(rx2);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -116,11 +838,11 @@ mod tests {
     fn ping_pong() {
         let (tx, mut rx) = channel::<usize>();
         // println!("tx {}", tx);
-        tx.send(43);
+        tx.send(43).unwrap();
         let tx2 = tx.clone();
         // println!("tx2 {}", tx);
-        tx2.send(434);
-        tx.send(4399);
+        tx2.send(434).unwrap();
+        tx.send(4399).unwrap();
         drop(tx2);
         // println!("tx2 after drop {}", tx);
         let result1 = rx.receive();
@@ -134,11 +856,11 @@ mod tests {
         let (tx, mut rx) = channel::<usize>();
         println!("tx {}", tx);
         let tx2 = tx.clone();
-        tx2.send(443);
+        tx2.send(443).unwrap();
         println!("num senders1 {}", rx);
         drop(tx);
         println!("num senders2 {}", rx);
-        tx2.send(4434);
+        tx2.send(4434).unwrap();
         println!("num senders2 {}", rx);
         drop(tx2);
         println!("num senders3 {}", rx);
@@ -149,4 +871,158 @@ mod tests {
         let result3 = rx.receive();
         assert_eq!(result3, None);
     }
+    #[test]
+    fn send_after_receivers_dropped() {
+        let (tx, rx) = channel::<usize>();
+        drop(rx);
+        let err = tx.send(43).unwrap_err();
+        assert_eq!(err.into_inner(), 43);
+    }
+    #[test]
+    fn recv_after_senders_dropped() {
+        let (tx, mut rx) = channel::<usize>();
+        tx.send(1).unwrap();
+        drop(tx);
+        assert_eq!(rx.recv(), Ok(1));
+        assert_eq!(rx.recv(), Err(RecvError));
+    }
+    #[test]
+    fn sync_channel_buffers_up_to_capacity() {
+        let (tx, mut rx) = sync_channel::<usize>(2);
+        tx.send(1).unwrap();
+        tx.send(2).unwrap();
+        assert_eq!(rx.recv(), Ok(1));
+        assert_eq!(rx.recv(), Ok(2));
+    }
+    #[test]
+    fn cloned_receivers_share_the_queue() {
+        use std::thread;
+
+        let (tx, rx) = channel::<usize>();
+        let rx2 = rx.clone();
+        for i in 0..4 {
+            tx.send(i).unwrap();
+        }
+        drop(tx);
+
+        let worker = |mut rx: Receiver<usize>| {
+            let mut items = Vec::new();
+            while let Ok(item) = rx.recv() {
+                items.push(item);
+            }
+            items
+        };
+        let handle = thread::spawn(move || worker(rx2));
+        let mut items = worker(rx);
+        items.extend(handle.join().unwrap());
+        items.sort();
+        assert_eq!(items, vec![0, 1, 2, 3]);
+    }
+    #[test]
+    fn sync_channel_rendezvous() {
+        use std::thread;
+
+        let (tx, mut rx) = sync_channel::<usize>(0);
+        let handle = thread::spawn(move || {
+            tx.send(7).unwrap();
+        });
+        assert_eq!(rx.recv(), Ok(7));
+        handle.join().unwrap();
+    }
+    #[test]
+    fn sync_send_unblocks_with_an_error_when_receiver_drops() {
+        use std::thread;
+
+        let (tx, rx) = sync_channel::<usize>(1);
+        tx.send(1).unwrap();
+        let tx2 = tx.clone();
+        let handle = thread::spawn(move || tx2.send(2));
+        // Give the spawned send a chance to actually block on the full queue
+        // before we drop the only receiver out from under it.
+        thread::sleep(Duration::from_millis(20));
+        drop(rx);
+        let err = handle.join().unwrap().unwrap_err();
+        assert_eq!(err.into_inner(), 2);
+    }
+    #[test]
+    fn try_recv_does_not_block() {
+        let (tx, mut rx) = channel::<usize>();
+        assert_eq!(rx.try_recv(), Err(TryRecvError::Empty));
+        tx.send(1).unwrap();
+        assert_eq!(rx.try_recv(), Ok(1));
+        drop(tx);
+        assert_eq!(rx.try_recv(), Err(TryRecvError::Disconnected));
+    }
+    #[test]
+    fn recv_timeout_times_out() {
+        let (tx, mut rx) = channel::<usize>();
+        assert_eq!(
+            rx.recv_timeout(Duration::from_millis(10)),
+            Err(RecvTimeoutError::Timeout)
+        );
+        tx.send(5).unwrap();
+        assert_eq!(rx.recv_timeout(Duration::from_millis(10)), Ok(5));
+    }
+    #[test]
+    fn receiver_into_iterator_drains_until_closed() {
+        let (tx, rx) = channel::<usize>();
+        tx.send(1).unwrap();
+        tx.send(2).unwrap();
+        drop(tx);
+        let items: Vec<_> = rx.into_iter().collect();
+        assert_eq!(items, vec![1, 2]);
+    }
+    #[test]
+    fn try_iter_stops_at_first_empty_state() {
+        let (tx, mut rx) = channel::<usize>();
+        tx.send(1).unwrap();
+        tx.send(2).unwrap();
+        let items: Vec<_> = rx.try_iter().collect();
+        assert_eq!(items, vec![1, 2]);
+    }
+    #[test]
+    fn select_picks_the_ready_channel() {
+        let (tx1, rx1) = channel::<usize>();
+        let (tx2, mut rx2) = channel::<usize>();
+        tx2.send(9).unwrap();
+
+        let mut select = Select::new();
+        let _idx1 = select.add(&rx1);
+        let idx2 = select.add(&rx2);
+
+        assert_eq!(select.ready(), idx2);
+        assert_eq!(rx2.try_recv(), Ok(9));
+        drop(tx1);
+    }
+    #[test]
+    fn select_blocks_until_a_sender_delivers() {
+        use std::thread;
+
+        let (tx, mut rx) = channel::<usize>();
+        let mut select = Select::new();
+        let idx = select.add(&rx);
+
+        let handle = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(20));
+            tx.send(1).unwrap();
+        });
+        assert_eq!(select.select(), idx);
+        assert_eq!(rx.try_recv(), Ok(1));
+        handle.join().unwrap();
+    }
+    #[test]
+    fn select_wakes_a_rendezvous_sync_sender() {
+        use std::thread;
+
+        let (tx, mut rx) = sync_channel::<usize>(0);
+        let mut select = Select::new();
+        let idx = select.add(&rx);
+
+        let handle = thread::spawn(move || {
+            tx.send(7).unwrap();
+        });
+        assert_eq!(select.select(), idx);
+        assert_eq!(rx.try_recv(), Ok(7));
+        handle.join().unwrap();
+    }
 }